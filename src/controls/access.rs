@@ -0,0 +1,83 @@
+use super::edge::PortId;
+use super::node::NodeWidget;
+use super::port::Port;
+
+/// Mirrors the handful of roles the editor actually needs; not a general
+/// accessibility taxonomy.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Role {
+    Group,
+    Button,
+    Port { connected: bool },
+}
+
+/// One entry in the semantic tree a screen reader walks. Built fresh from
+/// `NodeWidget`/`Port` each frame rather than cached, since it's cheap and
+/// keeps it from drifting out of sync with the visual tree.
+#[derive(Clone, Debug)]
+pub struct AccessNode {
+    pub id: String,
+    pub role: Role,
+    pub label: String,
+    pub children: Vec<AccessNode>,
+}
+
+impl AccessNode {
+    fn leaf(role: Role, label: String) -> Self {
+        Self {
+            id: String::new(),
+            role,
+            label,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl Port {
+    /// Accessibility node for this port on its own: name, data type and
+    /// whether an edge currently feeds it. The parent `NodeWidget` fills in
+    /// the id, since a port doesn't know its own index or owning node.
+    pub fn accessibility(&self, connected: bool) -> AccessNode {
+        let label = format!("{}, {:?}", self.name, self.ty);
+        AccessNode::leaf(Role::Port { connected }, label)
+    }
+}
+
+impl NodeWidget {
+    /// Assembles the semantic tree for this node: a labelled group holding
+    /// one child per input/output port plus the close action, all keyed off
+    /// the same `NodeId::to_string` the rest of the editor already uses so
+    /// the tree stays stable across frames.
+    pub fn accessibility(&self, mut connected: impl FnMut(bool, PortId) -> bool) -> AccessNode {
+        let id = self.id.to_string();
+
+        let mut children: Vec<AccessNode> = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(index, port)| {
+                let mut node = port.accessibility(connected(true, PortId(index)));
+                node.id = format!("{}-in-{}", id, index);
+                node
+            })
+            .collect();
+
+        children.extend(self.outputs.iter().enumerate().map(|(index, port)| {
+            let mut node = port.accessibility(connected(false, PortId(index)));
+            node.id = format!("{}-out-{}", id, index);
+            node
+        }));
+
+        children.push(AccessNode {
+            id: format!("{}-close", id),
+            ..AccessNode::leaf(Role::Button, "Close".to_owned())
+        });
+
+        AccessNode {
+            id,
+            role: Role::Group,
+            label: self.label().to_owned(),
+            children,
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::node::DataType;
+use crate::style::FONT_SIZE;
+use iced_wgpu::Renderer;
+use iced_winit::{text_input, Element, Length, Row, Text, TextInput};
+use serde::{Deserialize, Serialize};
+
+/// The inline literal shown in place of an optional input's socket when
+/// nothing feeds it, and what it lowers to in generated WGSL. Derives
+/// `Serialize`/`Deserialize` directly so `persist::NodeDoc` can store it
+/// without a separate on-disk mirror type.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DefaultValue {
+    Scalar(f32),
+    Vector2([f32; 2]),
+    Vector3([f32; 3]),
+    Vector4([f32; 4]),
+    Color([f32; 4]),
+}
+
+impl DefaultValue {
+    pub fn for_type(ty: DataType) -> Self {
+        match ty {
+            DataType::Scalar => DefaultValue::Scalar(0.0),
+            DataType::Vector2 => DefaultValue::Vector2([0.0; 2]),
+            DataType::Vector3 => DefaultValue::Vector3([0.0; 3]),
+            DataType::Vector4 => DefaultValue::Vector4([0.0; 4]),
+            DataType::Color => DefaultValue::Color([0.0, 0.0, 0.0, 1.0]),
+        }
+    }
+
+    /// The WGSL literal this default lowers to when its port is unconnected.
+    pub fn to_wgsl(&self) -> String {
+        match self {
+            DefaultValue::Scalar(v) => format!("{}", v),
+            DefaultValue::Vector2(v) => format!("vec2<f32>({}, {})", v[0], v[1]),
+            DefaultValue::Vector3(v) => format!("vec3<f32>({}, {}, {})", v[0], v[1], v[2]),
+            DefaultValue::Vector4(v) | DefaultValue::Color(v) => {
+                format!("vec4<f32>({}, {}, {}, {})", v[0], v[1], v[2], v[3])
+            }
+        }
+    }
+
+    fn components_mut(&mut self) -> &mut [f32] {
+        match self {
+            DefaultValue::Scalar(v) => std::slice::from_mut(v),
+            DefaultValue::Vector2(v) => v,
+            DefaultValue::Vector3(v) => v,
+            DefaultValue::Vector4(v) | DefaultValue::Color(v) => v,
+        }
+    }
+
+    pub fn set_component(&mut self, index: usize, value: f32) {
+        if let Some(slot) = self.components_mut().get_mut(index) {
+            *slot = value;
+        }
+    }
+}
+
+/// Per-component text-input state for one port's inline default editor: one
+/// `text_input::State` per component, so a `Vector4`/`Color` gets four and a
+/// `Scalar` gets one.
+#[derive(Default)]
+pub struct DefaultValueState {
+    components: Vec<text_input::State>,
+}
+
+impl DefaultValueState {
+    pub fn for_value(value: &DefaultValue) -> Self {
+        let len = value.components_len();
+        Self {
+            components: (0..len).map(|_| text_input::State::new()).collect(),
+        }
+    }
+}
+
+impl DefaultValue {
+    fn components_len(&self) -> usize {
+        match self {
+            DefaultValue::Scalar(_) => 1,
+            DefaultValue::Vector2(_) => 2,
+            DefaultValue::Vector3(_) => 3,
+            DefaultValue::Vector4(_) | DefaultValue::Color(_) => 4,
+        }
+    }
+
+    /// Owned copies of each component, so callers building a view don't hold
+    /// a borrow of `self` for the lifetime of the returned `Element`.
+    fn components(&self) -> Vec<f32> {
+        match self {
+            DefaultValue::Scalar(v) => vec![*v],
+            DefaultValue::Vector2(v) => v.to_vec(),
+            DefaultValue::Vector3(v) => v.to_vec(),
+            DefaultValue::Vector4(v) | DefaultValue::Color(v) => v.to_vec(),
+        }
+    }
+}
+
+/// Renders a number field per component — one for a scalar, up to four for a
+/// vector or color — each emitting `on_change` with its component index and
+/// parsed value as the user edits it. Colors additionally get a read-only
+/// hex preview alongside their four (R/G/B/A) fields.
+pub fn view<'a, M: 'a + Clone>(
+    value: &DefaultValue,
+    state: &'a mut DefaultValueState,
+    on_change: impl Fn(usize, f32) -> M + 'a + Copy,
+) -> Element<'a, M, Renderer> {
+    let fields = state
+        .components
+        .iter_mut()
+        .zip(value.components())
+        .enumerate()
+        .fold(Row::new().spacing(2), |row, (index, (field_state, value))| {
+            let field = TextInput::new(field_state, "0", &format!("{}", value), move |text| {
+                on_change(index, text.parse().unwrap_or(value))
+            })
+            .size(FONT_SIZE)
+            .width(Length::Units(48));
+            row.push(field)
+        });
+
+    match value {
+        DefaultValue::Color(components) => fields.push(color_swatch(*components)).into(),
+        _ => fields.into(),
+    }
+}
+
+fn color_swatch<'a, M: 'a>(components: [f32; 4]) -> Element<'a, M, Renderer> {
+    let [r, g, b, a] = components;
+    Text::new(format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        (r.clamp(0.0, 1.0) * 255.0) as u8,
+        (g.clamp(0.0, 1.0) * 255.0) as u8,
+        (b.clamp(0.0, 1.0) * 255.0) as u8,
+        (a.clamp(0.0, 1.0) * 255.0) as u8,
+    ))
+    .size(FONT_SIZE)
+    .into()
+}
@@ -0,0 +1,318 @@
+use super::access::AccessNode;
+use super::default_value::DefaultValue;
+use super::edge::{Pending, PortId};
+use super::history::{self, CommandHistory};
+use super::layout::{self, LayoutParams};
+use super::node::{EdgeKey, Graph, Message, NodeWidget};
+use super::persist;
+use crate::node::{Node, NodeId};
+use crate::style::FONT_SIZE;
+use iced_native::keyboard::KeyCode;
+use iced_wgpu::Renderer;
+use iced_winit::{button, Button, Element, Point, Row, Text};
+
+/// What keyboard focus currently rests on: a node's title/group, or one of
+/// its ports.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Focus {
+    Node(NodeId),
+    Port(NodeId, bool, PortId),
+}
+
+/// Owns the live [`Graph`] plus the editor-wide state `NodeWidget`/`Message`
+/// alone don't carry — principally the undo history. This is the call site
+/// every `Message` variant actually runs through; `NodeWidget::widget` only
+/// describes what a node looks like, it doesn't decide what a message does.
+pub struct NodeEditor {
+    pub graph: Graph,
+    pub layout: LayoutParams,
+    pub focus: Option<Focus>,
+    history: CommandHistory,
+    drag: Option<NodeId>,
+    /// The one endpoint already picked while the user is in the middle of
+    /// dragging out a new edge; resolved into a `Connect` once a compatible
+    /// opposite endpoint arrives, or dropped on `Message::CancelEdge`.
+    pending_edge: Option<Pending>,
+    registry: persist::Registry,
+    undo_button: button::State,
+    redo_button: button::State,
+    layout_button: button::State,
+}
+
+impl NodeEditor {
+    pub fn new(graph: Graph, registry: persist::Registry) -> Self {
+        Self {
+            graph,
+            layout: LayoutParams::default(),
+            focus: None,
+            history: CommandHistory::default(),
+            drag: None,
+            pending_edge: None,
+            registry,
+            undo_button: button::State::new(),
+            redo_button: button::State::new(),
+            layout_button: button::State::new(),
+        }
+    }
+
+    pub fn is_connected(&self, node: NodeId, port: PortId, is_input: bool) -> bool {
+        self.graph.edges.iter().any(|&(from, from_port, to, to_port)| {
+            if is_input {
+                to == node && to_port == port
+            } else {
+                from == node && from_port == port
+            }
+        })
+    }
+
+    /// Inserts a node through the undo history, optionally marking some of
+    /// its inputs optional (port index plus the literal it defaults to when
+    /// unconnected).
+    pub fn insert_node(
+        &mut self,
+        position: Point,
+        node: impl Into<Box<dyn Node>>,
+        optional_inputs: &[(usize, DefaultValue)],
+    ) -> NodeId {
+        // The widget doesn't know its real id until `AddNode` inserts it
+        // into the slotmap; `AddNode::apply` fixes up `widget.id` itself.
+        let widget = NodeWidget::new(NodeId::default(), position, node.into());
+        let id = self
+            .history
+            .push_add_node(history::AddNode::new(widget), &mut self.graph);
+
+        if let Some(widget) = self.graph.nodes.get_mut(id) {
+            for (index, default) in optional_inputs.iter().cloned() {
+                widget.set_optional_input(index, default);
+            }
+        }
+
+        id
+    }
+
+    pub fn connect(&mut self, edge: EdgeKey) {
+        self.history
+            .push(Box::new(history::Connect { edge }), &mut self.graph);
+    }
+
+    pub fn disconnect(&mut self, edge: EdgeKey) {
+        self.history
+            .push(Box::new(history::Disconnect { edge }), &mut self.graph);
+    }
+
+    /// Registers a node kind so `Message::Load` can reconstruct it; forwards
+    /// to the editor's own [`persist::Registry`] rather than exposing it
+    /// directly, same as `connect`/`disconnect` wrap the history.
+    pub fn register_kind(&mut self, kind: &'static str, ctor: fn() -> Box<dyn Node>) {
+        self.registry.register(kind, ctor);
+    }
+
+    /// Resolves one half of an edge drag: the first `StartEdge` just records
+    /// its endpoint, the second either completes the edge (if the two
+    /// endpoints are an input and an output) or replaces the pending one.
+    /// Starting again from an already-connected input instead lifts that
+    /// edge's existing source so the drag continues as if from there.
+    fn start_edge(&mut self, pending: Pending) {
+        if let Pending::Input(to, to_port) = pending {
+            if let Some(&edge) = self
+                .graph
+                .edges
+                .iter()
+                .find(|&&(_, _, node, port)| node == to && port == to_port)
+            {
+                self.disconnect(edge);
+                self.pending_edge = Some(Pending::Output(edge.0, edge.1));
+                return;
+            }
+        }
+
+        match (self.pending_edge.take(), pending) {
+            (Some(Pending::Output(from, from_port)), Pending::Input(to, to_port))
+            | (Some(Pending::Input(to, to_port)), Pending::Output(from, from_port)) => {
+                self.connect((from, from_port, to, to_port));
+            }
+            (_, pending) => self.pending_edge = Some(pending),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::DragStart(id) => {
+                self.drag = Some(id);
+                self.focus = Some(Focus::Node(id));
+                if let Some(widget) = self.graph.nodes.get_mut(id) {
+                    let position = widget.position;
+                    widget.drag_state.start(position);
+                }
+            }
+            Message::DragMove(position) => {
+                if let Some(id) = self.drag {
+                    if let Some(widget) = self.graph.nodes.get_mut(id) {
+                        widget.position = position;
+                    }
+                }
+            }
+            Message::DragEnd(id) => {
+                self.drag = None;
+                if let Some(widget) = self.graph.nodes.get_mut(id) {
+                    let position = widget.position;
+                    if let Some(delta) = widget.drag_state.end(position) {
+                        // `DragMove` already moved the node live; undo that
+                        // so the coalesced `MoveNode` command is the single
+                        // source of truth for the final position.
+                        widget.position = position - delta;
+                        self.history
+                            .push(Box::new(history::MoveNode { id, delta }), &mut self.graph);
+                    }
+                }
+            }
+            Message::Remove(id) => {
+                self.history
+                    .push(Box::new(history::RemoveNode::new(id)), &mut self.graph);
+                if self.focus == Some(Focus::Node(id)) {
+                    self.focus = None;
+                }
+            }
+            Message::Undo => self.history.undo(&mut self.graph),
+            Message::Redo => self.history.redo(&mut self.graph),
+            Message::StartEdge(pending) => self.start_edge(pending),
+            Message::CancelEdge => self.pending_edge = None,
+            Message::Dynamic(id, message) => {
+                if let Some(widget) = self.graph.nodes.get_mut(id) {
+                    widget.node.update(message.as_ref());
+                }
+            }
+            Message::AutoLayout => layout::auto_layout(&mut self.graph, self.layout),
+            Message::Save(path) => {
+                if let Err(err) = persist::save(&self.graph, path) {
+                    log::warn!("failed to save graph: {}", err);
+                }
+            }
+            Message::Load(path) => match persist::load(path, &self.registry) {
+                Ok(graph) => {
+                    self.graph = graph;
+                    self.history = CommandHistory::default();
+                    self.focus = None;
+                    self.pending_edge = None;
+                }
+                Err(err) => log::warn!("failed to load graph: {}", err),
+            },
+            Message::SetDefault(id, port, component, value) => {
+                if let Some(widget) = self.graph.nodes.get_mut(id) {
+                    if let Some(default) = widget
+                        .input_defaults
+                        .get_mut(port.0)
+                        .and_then(Option::as_mut)
+                    {
+                        default.set_component(component, value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Assembles the accessibility tree for every node, so a screen reader
+    /// can walk the whole graph in one pass.
+    pub fn accessibility_tree(&self) -> Vec<AccessNode> {
+        self.graph
+            .nodes
+            .iter()
+            .map(|(id, widget)| {
+                widget.accessibility(|is_input, port| self.is_connected(id, port, is_input))
+            })
+            .collect()
+    }
+
+    /// Moves keyboard focus to the next port within the current node, then
+    /// the next node's ports, wrapping back to the first node at the end.
+    pub fn focus_next(&mut self) {
+        let ids: Vec<NodeId> = self.graph.nodes.keys().collect();
+        if ids.is_empty() {
+            self.focus = None;
+            return;
+        }
+
+        self.focus = Some(match self.focus {
+            None => Focus::Node(ids[0]),
+            Some(Focus::Node(id)) => next_port_or_node(&self.graph, &ids, id, true, 0),
+            Some(Focus::Port(id, is_input, port)) => {
+                next_port_or_node(&self.graph, &ids, id, is_input, port.0 + 1)
+            }
+        });
+    }
+
+    /// Maps a key press against the current focus to an editor `Message`:
+    /// delete/backspace removes a focused node, enter/space begins an edge
+    /// from a focused port, tab advances focus.
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<Message> {
+        match key {
+            KeyCode::Tab => {
+                self.focus_next();
+                None
+            }
+            KeyCode::Delete | KeyCode::Backspace => match self.focus {
+                Some(Focus::Node(id)) => Some(Message::Remove(id)),
+                _ => None,
+            },
+            KeyCode::Enter | KeyCode::Space => match self.focus {
+                Some(Focus::Port(id, true, port)) => Some(Message::StartEdge(Pending::input(id, port))),
+                Some(Focus::Port(id, false, port)) => {
+                    Some(Message::StartEdge(Pending::output(id, port)))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Buttons for the editor actions that have no dedicated keyboard path:
+    /// undo/redo (disabled when the history has nothing to step to) and a
+    /// one-click auto-layout.
+    pub fn toolbar(&mut self) -> Element<'_, Message, Renderer> {
+        let can_undo = self.history.can_undo();
+        let can_redo = self.history.can_redo();
+
+        let mut undo = Button::new(&mut self.undo_button, Text::new("Undo").size(FONT_SIZE));
+        if can_undo {
+            undo = undo.on_press(Message::Undo);
+        }
+
+        let mut redo = Button::new(&mut self.redo_button, Text::new("Redo").size(FONT_SIZE));
+        if can_redo {
+            redo = redo.on_press(Message::Redo);
+        }
+
+        let layout = Button::new(
+            &mut self.layout_button,
+            Text::new("Auto Layout").size(FONT_SIZE),
+        )
+        .on_press(Message::AutoLayout);
+
+        Row::new().spacing(4).push(undo).push(redo).push(layout).into()
+    }
+}
+
+fn next_port_or_node(
+    graph: &Graph,
+    ids: &[NodeId],
+    current: NodeId,
+    is_input: bool,
+    next_port_index: usize,
+) -> Focus {
+    let widget = &graph.nodes[current];
+    let ports = if is_input { &widget.inputs } else { &widget.outputs };
+
+    if next_port_index < ports.len() {
+        return Focus::Port(current, is_input, PortId(next_port_index));
+    }
+    if is_input && !widget.outputs.is_empty() {
+        return Focus::Port(current, false, PortId(0));
+    }
+
+    let next_index = ids
+        .iter()
+        .position(|&id| id == current)
+        .map(|index| (index + 1) % ids.len())
+        .unwrap_or(0);
+    Focus::Node(ids[next_index])
+}
@@ -0,0 +1,227 @@
+use super::edge::PortId;
+use super::node::{EdgeKey, Graph, NodeWidget};
+use crate::node::NodeId;
+use iced_winit::Vector;
+
+/// A single reversible edit to the node [`Graph`].
+///
+/// Commands are applied once when pushed and are expected to be cheap to
+/// store, so they capture just enough state (an id, a delta, a removed
+/// widget) to reconstruct the graph on `undo` without diffing the whole tree.
+pub trait Command {
+    fn apply(&mut self, graph: &mut Graph);
+    fn undo(&mut self, graph: &mut Graph);
+}
+
+pub struct AddNode {
+    widget: Option<NodeWidget>,
+    id: Option<NodeId>,
+}
+
+impl AddNode {
+    pub fn new(widget: NodeWidget) -> Self {
+        Self {
+            widget: Some(widget),
+            id: None,
+        }
+    }
+
+    /// The id the node was actually inserted under, once `apply` has run.
+    pub fn inserted_id(&self) -> Option<NodeId> {
+        self.id
+    }
+}
+
+impl Command for AddNode {
+    fn apply(&mut self, graph: &mut Graph) {
+        let widget = self.widget.take().expect("AddNode applied twice in a row");
+        let id = graph.nodes.insert(widget);
+        // `insert` assigns the key; the widget's own `id` field (set before
+        // it had one) would otherwise be stale.
+        graph.nodes[id].id = id;
+        self.id = Some(id);
+    }
+
+    fn undo(&mut self, graph: &mut Graph) {
+        let id = self.id.take().expect("AddNode undone before it was applied");
+        self.widget = graph.nodes.remove(id);
+    }
+}
+
+pub struct RemoveNode {
+    id: NodeId,
+    widget: Option<NodeWidget>,
+    edges: Vec<EdgeKey>,
+}
+
+impl RemoveNode {
+    pub fn new(id: NodeId) -> Self {
+        Self {
+            id,
+            widget: None,
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl Command for RemoveNode {
+    fn apply(&mut self, graph: &mut Graph) {
+        self.edges = graph
+            .edges
+            .iter()
+            .copied()
+            .filter(|&(from, _, to, _)| from == self.id || to == self.id)
+            .collect();
+        graph
+            .edges
+            .retain(|&(from, _, to, _)| from != self.id && to != self.id);
+        self.widget = graph.nodes.remove(self.id);
+    }
+
+    fn undo(&mut self, graph: &mut Graph) {
+        let widget = self
+            .widget
+            .take()
+            .expect("RemoveNode undone before it was applied");
+        let old_id = self.id;
+        let new_id = graph.nodes.insert(widget);
+        // Same fix-up as `AddNode::apply`: the widget still carries its old key.
+        graph.nodes[new_id].id = new_id;
+        self.id = new_id;
+        // Slotmap hands out a fresh key on every insert, so edges captured
+        // under the old id have to be remapped before they're valid again.
+        for edge in &mut self.edges {
+            if edge.0 == old_id {
+                edge.0 = new_id;
+            }
+            if edge.2 == old_id {
+                edge.2 = new_id;
+            }
+        }
+        graph.edges.extend(self.edges.drain(..));
+    }
+}
+
+pub struct MoveNode {
+    pub id: NodeId,
+    pub delta: Vector,
+}
+
+impl Command for MoveNode {
+    fn apply(&mut self, graph: &mut Graph) {
+        if let Some(node) = graph.nodes.get_mut(self.id) {
+            node.position = node.position + self.delta;
+        }
+    }
+
+    fn undo(&mut self, graph: &mut Graph) {
+        if let Some(node) = graph.nodes.get_mut(self.id) {
+            node.position = node.position - self.delta;
+        }
+    }
+}
+
+pub struct Connect {
+    pub edge: EdgeKey,
+}
+
+impl Command for Connect {
+    fn apply(&mut self, graph: &mut Graph) {
+        graph.edges.push(self.edge);
+    }
+
+    fn undo(&mut self, graph: &mut Graph) {
+        graph.edges.retain(|&edge| edge != self.edge);
+    }
+}
+
+pub struct Disconnect {
+    pub edge: EdgeKey,
+}
+
+impl Command for Disconnect {
+    fn apply(&mut self, graph: &mut Graph) {
+        graph.edges.retain(|&edge| edge != self.edge);
+    }
+
+    fn undo(&mut self, graph: &mut Graph) {
+        graph.edges.push(self.edge);
+    }
+}
+
+/// Linear undo/redo stack with an insertion cursor.
+///
+/// Pushing a new command past the cursor (i.e. after one or more `undo`
+/// calls) discards the redo tail, matching how most editors treat history
+/// once the user branches off from it.
+#[derive(Default)]
+pub struct CommandHistory {
+    commands: Vec<Box<dyn Command>>,
+    cursor: usize,
+}
+
+impl CommandHistory {
+    pub fn push(&mut self, mut command: Box<dyn Command>, graph: &mut Graph) {
+        command.apply(graph);
+        self.commands.truncate(self.cursor);
+        self.commands.push(command);
+        self.cursor = self.commands.len();
+    }
+
+    /// Like `push`, but for `AddNode` specifically: callers need the id the
+    /// graph assigned the new node, which is only known after `apply` runs
+    /// and isn't recoverable from a type-erased `Box<dyn Command>`.
+    pub fn push_add_node(&mut self, mut command: AddNode, graph: &mut Graph) -> NodeId {
+        command.apply(graph);
+        let id = command
+            .inserted_id()
+            .expect("AddNode::apply always assigns an id");
+        self.commands.truncate(self.cursor);
+        self.commands.push(Box::new(command));
+        self.cursor = self.commands.len();
+        id
+    }
+
+    pub fn undo(&mut self, graph: &mut Graph) {
+        if self.cursor == 0 {
+            return;
+        }
+        self.cursor -= 1;
+        self.commands[self.cursor].undo(graph);
+    }
+
+    pub fn redo(&mut self, graph: &mut Graph) {
+        if self.cursor == self.commands.len() {
+            return;
+        }
+        self.commands[self.cursor].apply(graph);
+        self.cursor += 1;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor < self.commands.len()
+    }
+}
+
+/// Accumulates a node drag so the editor can push a single coalesced
+/// [`MoveNode`] command on release instead of one per mouse-move event.
+#[derive(Default)]
+pub struct DragState {
+    origin: Option<iced_winit::Point>,
+}
+
+impl DragState {
+    pub fn start(&mut self, position: iced_winit::Point) {
+        self.origin = Some(position);
+    }
+
+    /// Consumes the drag, returning the total delta since `start` if one was
+    /// in progress.
+    pub fn end(&mut self, position: iced_winit::Point) -> Option<Vector> {
+        self.origin.take().map(|origin| position - origin)
+    }
+}
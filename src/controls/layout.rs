@@ -0,0 +1,160 @@
+use super::node::{Graph, NodeWidget};
+use crate::node::NodeId;
+use crate::style::FONT_SIZE;
+use iced_winit::Point;
+use std::collections::HashMap;
+
+/// Spacing between layers/nodes for [`auto_layout`]. Distances are in the
+/// same units as `NodeWidget::position`.
+#[derive(Clone, Copy)]
+pub struct LayoutParams {
+    pub h_gap: f32,
+    pub v_gap: f32,
+}
+
+impl Default for LayoutParams {
+    fn default() -> Self {
+        Self {
+            h_gap: 48.0,
+            v_gap: 24.0,
+        }
+    }
+}
+
+/// Layered left-to-right ("Sugiyama-style") auto-layout: nodes are grouped
+/// into layers by longest path from a source node, ordered within each layer
+/// to reduce edge crossings, then stacked into `position`s.
+pub fn auto_layout(graph: &mut Graph, params: LayoutParams) {
+    let layer = assign_layers(graph);
+    let ordered = order_layers(graph, &layer);
+
+    let max_width = graph
+        .nodes
+        .values()
+        .map(|widget| widget.node.desc().width as f32)
+        .fold(0.0, f32::max);
+
+    for (layer_index, nodes) in ordered.iter().enumerate() {
+        let x = layer_index as f32 * (max_width + params.h_gap);
+        let mut y = 0.0;
+        for &id in nodes {
+            let height = graph
+                .nodes
+                .get(id)
+                .map(estimate_height)
+                .unwrap_or_default();
+            if let Some(widget) = graph.nodes.get_mut(id) {
+                widget.position = Point::new(x, y);
+            }
+            y += height + params.v_gap;
+        }
+    }
+}
+
+/// Assigns each node a layer via longest-path: sources (no incoming edges)
+/// land on layer 0, every other node is one past the deepest predecessor.
+/// Relaxation is capped at `node_count` passes so a cyclic graph still
+/// terminates instead of looping forever.
+fn assign_layers(graph: &Graph) -> HashMap<NodeId, usize> {
+    let mut layer: HashMap<NodeId, usize> = graph.nodes.keys().map(|id| (id, 0)).collect();
+
+    for _ in 0..graph.nodes.len() {
+        let mut changed = false;
+        for &(from, _, to, _) in &graph.edges {
+            let next = layer[&from] + 1;
+            if next > layer[&to] {
+                layer.insert(to, next);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    layer
+}
+
+/// Orders nodes within each layer using the iterated median heuristic:
+/// alternating down/up sweeps move each node towards the median position of
+/// its neighbors in the adjacent layer, then the layer is re-sorted by that
+/// key. Nodes with no neighbors in the swept-from layer are placed after the
+/// others, keeping their relative order among themselves.
+fn order_layers(graph: &Graph, layer: &HashMap<NodeId, usize>) -> Vec<Vec<NodeId>> {
+    let layer_count = layer.values().copied().max().map_or(0, |max| max + 1);
+    let mut layers: Vec<Vec<NodeId>> = vec![Vec::new(); layer_count];
+    for (&id, &index) in layer {
+        layers[index].push(id);
+    }
+
+    const SWEEPS: usize = 4;
+    for sweep in 0..SWEEPS {
+        if sweep % 2 == 0 {
+            for li in 1..layers.len() {
+                reorder_by_median(graph, &mut layers, li, true);
+            }
+        } else {
+            for li in (0..layers.len().saturating_sub(1)).rev() {
+                reorder_by_median(graph, &mut layers, li, false);
+            }
+        }
+    }
+
+    layers
+}
+
+fn reorder_by_median(graph: &Graph, layers: &mut [Vec<NodeId>], li: usize, use_prev_layer: bool) {
+    let neighbor_layer = if use_prev_layer { li - 1 } else { li + 1 };
+    let neighbor_index: HashMap<NodeId, usize> = layers[neighbor_layer]
+        .iter()
+        .enumerate()
+        .map(|(index, &id)| (id, index))
+        .collect();
+    let original_index: HashMap<NodeId, usize> = layers[li]
+        .iter()
+        .enumerate()
+        .map(|(index, &id)| (id, index))
+        .collect();
+
+    let mut keyed: Vec<(Option<usize>, NodeId)> = layers[li]
+        .iter()
+        .map(|&id| {
+            let mut positions: Vec<usize> = graph
+                .edges
+                .iter()
+                .filter_map(|&(from, _, to, _)| {
+                    if use_prev_layer && to == id {
+                        neighbor_index.get(&from).copied()
+                    } else if !use_prev_layer && from == id {
+                        neighbor_index.get(&to).copied()
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            positions.sort_unstable();
+            let median = positions.get(positions.len() / 2).copied();
+            (median, id)
+        })
+        .collect();
+
+    keyed.sort_by(|a, b| match (a.0, b.0) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => original_index[&a.1].cmp(&original_index[&b.1]),
+    });
+
+    layers[li] = keyed.into_iter().map(|(_, id)| id).collect();
+}
+
+/// `NodeWidget` has no explicit height field (it's `Length::Shrink` and laid
+/// out by iced), so layout estimates one from the same inputs that drive
+/// `desc().width`: a title row plus one row per input/output slot.
+fn estimate_height(widget: &NodeWidget) -> f32 {
+    const TITLE_HEIGHT: f32 = FONT_SIZE as f32 + 4.0;
+    const ROW_HEIGHT: f32 = FONT_SIZE as f32 + 2.0;
+
+    let rows = widget.inputs.len().max(widget.outputs.len()) as f32;
+    TITLE_HEIGHT + ROW_HEIGHT * rows
+}
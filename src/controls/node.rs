@@ -1,4 +1,5 @@
 use super::{
+    default_value::{self, DefaultValue, DefaultValueState},
     edge::{Pending, PortId},
     port::Port,
 };
@@ -9,9 +10,24 @@ use iced_wgpu::Renderer;
 use iced_winit::{
     alignment, Alignment, Column, Container, Element, Length, Point, Row, Rule, Space, Text,
 };
+use std::path::PathBuf;
 
 pub type NodeMap = slotmap::SlotMap<NodeId, NodeWidget>;
 
+/// A connection between an output port and an input port, named for the
+/// endpoints it joins rather than an opaque id so commands and serialization
+/// can compare edges by value.
+pub type EdgeKey = (NodeId, PortId, NodeId, PortId);
+
+/// The full persistent state of the node editor: every node plus the edges
+/// between them. [`super::history::Command`]s and save/load both operate on
+/// this rather than on `NodeMap` alone, since neither nodes nor edges are
+/// meaningful in isolation.
+pub struct Graph {
+    pub nodes: NodeMap,
+    pub edges: Vec<EdgeKey>,
+}
+
 impl ToString for NodeId {
     fn to_string(&self) -> String {
         let value = slotmap::Key::data(self).as_ffi();
@@ -32,6 +48,18 @@ pub enum Message {
 
     StartEdge(Pending),
     CancelEdge,
+
+    Undo,
+    Redo,
+
+    AutoLayout,
+
+    Save(PathBuf),
+    Load(PathBuf),
+
+    /// `usize` is the component index within the port's default value
+    /// (always 0 for scalars).
+    SetDefault(NodeId, PortId, usize, f32),
 }
 
 pub struct NodeWidget {
@@ -46,12 +74,23 @@ pub struct NodeWidget {
     pub title_state: pad::State,
     pub close: pad::State,
     pub drag: pad::State,
+
+    /// Accumulates the in-progress drag so `DragEnd` can push a single
+    /// coalesced `MoveNode` command. `DragMove` still moves `position` live
+    /// for feedback; `DragEnd` rewinds that before pushing the command.
+    pub drag_state: super::history::DragState,
+
+    /// Parallel to `inputs`: `Some` marks that input optional and holds the
+    /// inline literal fed to codegen while it's unconnected.
+    pub input_defaults: Vec<Option<DefaultValue>>,
+    input_default_state: Vec<DefaultValueState>,
 }
 
 impl NodeWidget {
     pub fn new(id: NodeId, position: Point, node: impl Into<Box<dyn crate::node::Node>>) -> Self {
         let node = node.into();
         let desc = node.desc();
+        let input_count = desc.inputs.len();
         Self {
             id,
             position,
@@ -69,6 +108,9 @@ impl NodeWidget {
             title_state: Default::default(),
             close: Default::default(),
             drag: Default::default(),
+            drag_state: Default::default(),
+            input_defaults: vec![None; input_count],
+            input_default_state: (0..input_count).map(|_| Default::default()).collect(),
         }
     }
 
@@ -76,7 +118,16 @@ impl NodeWidget {
         self.node.desc().label
     }
 
-    pub fn widget(&mut self) -> Element<Message, Renderer> {
+    /// Marks an input optional, so it renders an inline default-value editor
+    /// instead of an empty socket whenever nothing is connected to it.
+    pub fn set_optional_input(&mut self, index: usize, default: DefaultValue) {
+        if let Some(slot) = self.input_defaults.get_mut(index) {
+            self.input_default_state[index] = DefaultValueState::for_value(&default);
+            *slot = Some(default);
+        }
+    }
+
+    pub fn widget(&mut self, connected: impl Fn(PortId) -> bool) -> Element<Message, Renderer> {
         let node = self.id;
 
         fn text_center(label: &str) -> Text<Renderer> {
@@ -144,7 +195,28 @@ impl NodeWidget {
                 .push(close)
         };
 
-        let inputs = create_ports(node, &mut self.inputs, Pending::input);
+        let inputs = self.inputs.iter_mut().enumerate().zip(
+            self.input_defaults.iter_mut().zip(self.input_default_state.iter_mut()),
+        ).fold(
+            Column::new().width(Length::Fill).spacing(2).padding([4, 0]),
+            |column, ((index, port), (default, default_state))| {
+                let port_id = PortId(index);
+                let row = match default {
+                    Some(default) if !connected(port_id) => default_value::view(
+                        default,
+                        default_state,
+                        move |component, value| Message::SetDefault(node, port_id, component, value),
+                    ),
+                    _ => port.view(Pending::input(node, port_id)),
+                };
+                column.push(row)
+            },
+        );
+        let inputs: Element<Message, Renderer> = if self.inputs.is_empty() {
+            Space::new(Length::Shrink, Length::Shrink).into()
+        } else {
+            inputs.into()
+        };
         let outputs = create_ports(node, &mut self.outputs, Pending::output);
 
         let rule = Rule::horizontal(0).style(style::Node);
@@ -0,0 +1,264 @@
+use super::default_value::DefaultValue;
+use super::edge::PortId;
+use super::node::{Graph, NodeMap, NodeWidget};
+use crate::node::{Node, NodeId};
+use iced_winit::Point;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize)]
+struct NodeDoc {
+    id: String,
+    kind: String,
+    position: (f32, f32),
+    params: serde_json::Value,
+    /// Mirrors `NodeWidget::input_defaults`: parallel to the node's inputs,
+    /// `Some` for the ones marked optional.
+    defaults: Vec<Option<DefaultValue>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EdgeDoc {
+    from_node: String,
+    from_port: usize,
+    to_node: String,
+    to_port: usize,
+}
+
+/// On-disk form of a [`Graph`]. Node ids are stored as the `idx v version`
+/// strings from `ToString for NodeId` purely so edges can reference the
+/// right node within the document; on load they're remapped to whatever new
+/// `NodeId`s `NodeMap::insert` hands out; rather than reconstructed.
+#[derive(Serialize, Deserialize)]
+struct Document {
+    nodes: Vec<NodeDoc>,
+    edges: Vec<EdgeDoc>,
+}
+
+/// Maps a node's `kind()` string to a constructor for its default state, so
+/// a saved document can rebuild the right trait object on load.
+#[derive(Default)]
+pub struct Registry {
+    ctors: HashMap<&'static str, fn() -> Box<dyn Node>>,
+}
+
+impl Registry {
+    pub fn register(&mut self, kind: &'static str, ctor: fn() -> Box<dyn Node>) {
+        self.ctors.insert(kind, ctor);
+    }
+
+    fn construct(&self, kind: &str) -> Result<Box<dyn Node>, Error> {
+        self.ctors
+            .get(kind)
+            .map(|ctor| ctor())
+            .ok_or_else(|| Error::UnknownKind(kind.to_owned()))
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    UnknownKind(String),
+    DanglingEdge(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Json(err) => write!(f, "{}", err),
+            Error::UnknownKind(kind) => write!(f, "no registered node kind `{}`", kind),
+            Error::DanglingEdge(id) => write!(f, "edge references unknown node `{}`", id),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+pub fn save(graph: &Graph, path: impl AsRef<Path>) -> Result<(), Error> {
+    let nodes = graph
+        .nodes
+        .iter()
+        .map(|(id, widget)| NodeDoc {
+            id: id.to_string(),
+            kind: widget.node.kind().to_owned(),
+            position: (widget.position.x, widget.position.y),
+            params: widget.node.save_params(),
+            defaults: widget.input_defaults.clone(),
+        })
+        .collect();
+
+    let edges = graph
+        .edges
+        .iter()
+        .map(|&(from, from_port, to, to_port)| EdgeDoc {
+            from_node: from.to_string(),
+            from_port: from_port.0,
+            to_node: to.to_string(),
+            to_port: to_port.0,
+        })
+        .collect();
+
+    let document = Document { nodes, edges };
+    let text = serde_json::to_string_pretty(&document)?;
+    std::fs::write(path, text)?;
+    Ok(())
+}
+
+pub fn load(path: impl AsRef<Path>, registry: &Registry) -> Result<Graph, Error> {
+    let text = std::fs::read_to_string(path)?;
+    let document: Document = serde_json::from_str(&text)?;
+
+    let mut nodes = NodeMap::default();
+    let mut id_map = HashMap::with_capacity(document.nodes.len());
+    for node_doc in &document.nodes {
+        let mut node = registry.construct(&node_doc.kind)?;
+        node.load_params(&node_doc.params);
+        let position = Point::new(node_doc.position.0, node_doc.position.1);
+        let id = nodes.insert_with_key(|id| NodeWidget::new(id, position, node));
+        for (index, default) in node_doc.defaults.iter().cloned().enumerate() {
+            if let Some(default) = default {
+                nodes[id].set_optional_input(index, default);
+            }
+        }
+        id_map.insert(node_doc.id.clone(), id);
+    }
+
+    let mut edges = Vec::with_capacity(document.edges.len());
+    for edge_doc in &document.edges {
+        let from = resolve(&id_map, &edge_doc.from_node)?;
+        let to = resolve(&id_map, &edge_doc.to_node)?;
+        edges.push((from, PortId(edge_doc.from_port), to, PortId(edge_doc.to_port)));
+    }
+
+    Ok(Graph { nodes, edges })
+}
+
+fn resolve(id_map: &HashMap<String, NodeId>, saved_id: &str) -> Result<NodeId, Error> {
+    id_map
+        .get(saved_id)
+        .copied()
+        .ok_or_else(|| Error::DanglingEdge(saved_id.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::{DataType, DynMessage, NodeDesc};
+
+    struct DummyNode {
+        desc: NodeDesc,
+        value: f32,
+    }
+
+    impl DummyNode {
+        fn new() -> Self {
+            Self {
+                desc: NodeDesc {
+                    label: "Dummy",
+                    width: 120,
+                    inputs: vec![("in", DataType::Scalar)],
+                    outputs: vec![("out", DataType::Scalar)],
+                },
+                value: 0.0,
+            }
+        }
+    }
+
+    impl Node for DummyNode {
+        fn desc(&self) -> &NodeDesc {
+            &self.desc
+        }
+
+        fn view(&mut self, _id: NodeId) -> iced_winit::Element<'_, Box<dyn DynMessage>, iced_wgpu::Renderer> {
+            iced_winit::Text::new(self.desc.label).into()
+        }
+
+        fn kind(&self) -> &'static str {
+            "dummy"
+        }
+
+        fn save_params(&self) -> serde_json::Value {
+            serde_json::json!({ "value": self.value })
+        }
+
+        fn load_params(&mut self, params: &serde_json::Value) {
+            if let Some(value) = params.get("value").and_then(|v| v.as_f64()) {
+                self.value = value as f32;
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips_nodes_and_edges() {
+        let mut graph = Graph {
+            nodes: NodeMap::default(),
+            edges: Vec::new(),
+        };
+        let a = graph
+            .nodes
+            .insert_with_key(|id| NodeWidget::new(id, Point::new(0.0, 0.0), DummyNode::new()));
+        let b = graph
+            .nodes
+            .insert_with_key(|id| NodeWidget::new(id, Point::new(100.0, 50.0), DummyNode::new()));
+        graph.nodes[a].node.load_params(&serde_json::json!({ "value": 2.5 }));
+        graph.nodes[a].set_optional_input(0, DefaultValue::Scalar(3.5));
+        graph.edges.push((a, PortId(0), b, PortId(0)));
+
+        let path = std::env::temp_dir().join(format!(
+            "wgsl-shaderlab-persist-test-{}.json",
+            std::process::id()
+        ));
+
+        let mut registry = Registry::default();
+        registry.register("dummy", || Box::new(DummyNode::new()));
+
+        save(&graph, &path).expect("save should succeed");
+        let loaded = load(&path, &registry).expect("load should succeed");
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.nodes.len(), graph.nodes.len());
+        assert_eq!(loaded.edges.len(), 1);
+
+        let mut original_positions: Vec<(f32, f32)> = graph
+            .nodes
+            .values()
+            .map(|widget| (widget.position.x, widget.position.y))
+            .collect();
+        let mut loaded_positions: Vec<(f32, f32)> = loaded
+            .nodes
+            .values()
+            .map(|widget| (widget.position.x, widget.position.y))
+            .collect();
+        original_positions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        loaded_positions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(original_positions, loaded_positions);
+
+        let (from, from_port, to, to_port) = loaded.edges[0];
+        assert_eq!(from_port, PortId(0));
+        assert_eq!(to_port, PortId(0));
+        assert!(loaded.nodes.contains_key(from));
+        assert!(loaded.nodes.contains_key(to));
+
+        let loaded_a = loaded
+            .nodes
+            .values()
+            .find(|widget| widget.position == Point::new(0.0, 0.0))
+            .expect("node at the origin round-trips");
+        assert_eq!(loaded_a.input_defaults[0], Some(DefaultValue::Scalar(3.5)));
+    }
+}
@@ -0,0 +1,410 @@
+//! A tiny embedded Lisp used to define node kinds without recompiling the
+//! crate. A script declares its ports, optional tweakable `param`s, and a
+//! body; the body is evaluated against the WGSL expression strings of its
+//! connected inputs (falling back to a param's live value by name) to
+//! produce the WGSL expression this node emits.
+use crate::node::{DataType, DynMessage, Node, NodeDesc, NodeId};
+use crate::style::FONT_SIZE;
+use iced_wgpu::Renderer;
+use iced_winit::{text_input, Column, Element, Length, Row, Text, TextInput};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Symbol(String),
+    Number(f64),
+    Str(String),
+    List(Vec<Expr>),
+}
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Io(std::io::Error),
+    Parse(String),
+    Eval(String),
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptError::Io(err) => write!(f, "{}", err),
+            ScriptError::Parse(msg) => write!(f, "parse error: {}", msg),
+            ScriptError::Eval(msg) => write!(f, "eval error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+impl From<std::io::Error> for ScriptError {
+    fn from(err: std::io::Error) -> Self {
+        ScriptError::Io(err)
+    }
+}
+
+fn tokenize(source: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '(' | ')' => {
+                tokens.push(c.to_string());
+                chars.next();
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '"' => {
+                chars.next();
+                let mut s = String::new();
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        break;
+                    }
+                    s.push(c);
+                }
+                tokens.push(format!("\"{}", s));
+            }
+            _ => {
+                let mut s = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                tokens.push(s);
+            }
+        }
+    }
+    tokens
+}
+
+fn parse_all(source: &str) -> Result<Vec<Expr>, ScriptError> {
+    let tokens = tokenize(source);
+    let mut pos = 0;
+    let mut exprs = Vec::new();
+    while pos < tokens.len() {
+        exprs.push(parse_one(&tokens, &mut pos)?);
+    }
+    Ok(exprs)
+}
+
+fn parse_one(tokens: &[String], pos: &mut usize) -> Result<Expr, ScriptError> {
+    let token = tokens
+        .get(*pos)
+        .ok_or_else(|| ScriptError::Parse("unexpected end of input".into()))?;
+    *pos += 1;
+
+    if token == "(" {
+        let mut items = Vec::new();
+        loop {
+            match tokens.get(*pos) {
+                Some(t) if t == ")" => {
+                    *pos += 1;
+                    break;
+                }
+                Some(_) => items.push(parse_one(tokens, pos)?),
+                None => return Err(ScriptError::Parse("unterminated list".into())),
+            }
+        }
+        Ok(Expr::List(items))
+    } else if token == ")" {
+        Err(ScriptError::Parse("unexpected `)`".into()))
+    } else if let Some(rest) = token.strip_prefix('"') {
+        Ok(Expr::Str(rest.to_owned()))
+    } else if let Ok(n) = token.parse::<f64>() {
+        Ok(Expr::Number(n))
+    } else {
+        Ok(Expr::Symbol(token.clone()))
+    }
+}
+
+fn parse_data_type(name: &str) -> Result<DataType, ScriptError> {
+    // Matched by name against the handwritten script text rather than
+    // derived via serde, since there's no on-disk document format here.
+    match name {
+        "Scalar" => Ok(DataType::Scalar),
+        "Vector2" => Ok(DataType::Vector2),
+        "Vector3" => Ok(DataType::Vector3),
+        "Vector4" => Ok(DataType::Vector4),
+        "Color" => Ok(DataType::Color),
+        other => Err(ScriptError::Parse(format!("unknown port type `{}`", other))),
+    }
+}
+
+struct PortDecl {
+    name: String,
+    ty: DataType,
+}
+
+/// Parsed form of a script file: its declared ports, the exposed parameters
+/// a user can tweak per-instance (name plus default literal), and the body
+/// expression evaluated at codegen time.
+pub struct Script {
+    label: String,
+    inputs: Vec<PortDecl>,
+    outputs: Vec<PortDecl>,
+    params: Vec<(String, f64)>,
+    body: Expr,
+}
+
+impl Script {
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        let mut label = None;
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut params = Vec::new();
+        let mut body = None;
+
+        for expr in parse_all(source)? {
+            let Expr::List(items) = expr else {
+                return Err(ScriptError::Parse("expected a top-level form".into()));
+            };
+            let head = match items.first() {
+                Some(Expr::Symbol(s)) => s.as_str(),
+                _ => return Err(ScriptError::Parse("expected a form name".into())),
+            };
+            match head {
+                "label" => {
+                    label = Some(expect_str(&items, 1)?);
+                }
+                "input" => inputs.push(PortDecl {
+                    name: expect_str(&items, 1)?,
+                    ty: parse_data_type(&expect_sym(&items, 2)?)?,
+                }),
+                "output" => outputs.push(PortDecl {
+                    name: expect_str(&items, 1)?,
+                    ty: parse_data_type(&expect_sym(&items, 2)?)?,
+                }),
+                "param" => params.push((expect_str(&items, 1)?, expect_number(&items, 2)?)),
+                "body" => {
+                    body = Some(
+                        items
+                            .get(1)
+                            .cloned()
+                            .ok_or_else(|| ScriptError::Parse("empty `body` form".into()))?,
+                    );
+                }
+                other => return Err(ScriptError::Parse(format!("unknown form `{}`", other))),
+            }
+        }
+
+        Ok(Script {
+            label: label.ok_or_else(|| ScriptError::Parse("missing `label` form".into()))?,
+            inputs,
+            outputs,
+            params,
+            body: body.ok_or_else(|| ScriptError::Parse("missing `body` form".into()))?,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ScriptError> {
+        Script::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Evaluates the body against the WGSL expression strings already
+    /// wired to each input, producing the WGSL expression this node emits.
+    fn codegen(&self, inputs: &HashMap<&str, String>) -> Result<String, ScriptError> {
+        eval(&self.body, inputs)
+    }
+}
+
+fn expect_str(items: &[Expr], index: usize) -> Result<String, ScriptError> {
+    match items.get(index) {
+        Some(Expr::Str(s)) => Ok(s.clone()),
+        _ => Err(ScriptError::Parse(format!("expected a string at position {}", index))),
+    }
+}
+
+fn expect_sym(items: &[Expr], index: usize) -> Result<String, ScriptError> {
+    match items.get(index) {
+        Some(Expr::Symbol(s)) => Ok(s.clone()),
+        _ => Err(ScriptError::Parse(format!("expected a symbol at position {}", index))),
+    }
+}
+
+fn expect_number(items: &[Expr], index: usize) -> Result<f64, ScriptError> {
+    match items.get(index) {
+        Some(Expr::Number(n)) => Ok(*n),
+        _ => Err(ScriptError::Parse(format!("expected a number at position {}", index))),
+    }
+}
+
+/// Evaluates a body expression into a WGSL snippet: symbols resolve to the
+/// caller-supplied input expressions, numbers/strings render literally, and
+/// `(op a b ...)` lists render as parenthesized WGSL operator/call
+/// expressions.
+fn eval(expr: &Expr, inputs: &HashMap<&str, String>) -> Result<String, ScriptError> {
+    match expr {
+        Expr::Number(n) => Ok(format!("{}", n)),
+        Expr::Str(s) => Ok(s.clone()),
+        Expr::Symbol(s) => inputs
+            .get(s.as_str())
+            .cloned()
+            .ok_or_else(|| ScriptError::Eval(format!("unbound input `{}`", s))),
+        Expr::List(items) => {
+            let head = match items.first() {
+                Some(Expr::Symbol(s)) => s.as_str(),
+                _ => return Err(ScriptError::Eval("expected an operator symbol".into())),
+            };
+            let args = items[1..]
+                .iter()
+                .map(|arg| eval(arg, inputs))
+                .collect::<Result<Vec<_>, _>>()?;
+            match head {
+                "+" | "-" | "*" | "/" => Ok(format!("({})", args.join(&format!(" {} ", head)))),
+                call => Ok(format!("{}({})", call, args.join(", "))),
+            }
+        }
+    }
+}
+
+/// Emitted by a [`ScriptNode`]'s own parameter fields; routed back into
+/// `values` through `Node::update` rather than mutating state from `view`.
+#[derive(Clone, Debug)]
+struct SetParam(usize, f32);
+
+/// A node kind whose shape and code generation are driven entirely by a
+/// loaded [`Script`] rather than a compiled `impl Node`. The script's own
+/// `param` declarations become this instance's live, editable `values`.
+pub struct ScriptNode {
+    desc: NodeDesc,
+    script: std::rc::Rc<Script>,
+    values: Vec<f32>,
+    states: Vec<text_input::State>,
+}
+
+impl ScriptNode {
+    pub fn new(script: Script) -> Self {
+        // Scripts are scanned once at startup and live for the process, so
+        // leaking their (otherwise owned) strings to get `&'static str` for
+        // `NodeDesc` is a deliberate trade rather than an oversight.
+        let label: &'static str = Box::leak(script.label.clone().into_boxed_str());
+        let inputs = script
+            .inputs
+            .iter()
+            .map(|port| {
+                let name: &'static str = Box::leak(port.name.clone().into_boxed_str());
+                (name, port.ty)
+            })
+            .collect();
+        let outputs = script
+            .outputs
+            .iter()
+            .map(|port| {
+                let name: &'static str = Box::leak(port.name.clone().into_boxed_str());
+                (name, port.ty)
+            })
+            .collect();
+
+        let values = script.params.iter().map(|&(_, default)| default as f32).collect();
+        let states = script.params.iter().map(|_| text_input::State::new()).collect();
+
+        Self {
+            desc: NodeDesc {
+                label,
+                width: 160,
+                inputs,
+                outputs,
+            },
+            script: std::rc::Rc::new(script),
+            values,
+            states,
+        }
+    }
+
+    /// Codegen inputs, plus each live parameter value under its own name so
+    /// the body can reference a `param` the same way it references a
+    /// connected input.
+    pub fn codegen(&self, inputs: &HashMap<&str, String>) -> Result<String, ScriptError> {
+        let mut inputs = inputs.clone();
+        for (&value, (name, _)) in self.values.iter().zip(self.script.params.iter()) {
+            inputs.entry(name.as_str()).or_insert_with(|| format!("{}", value));
+        }
+        self.script.codegen(&inputs)
+    }
+}
+
+impl Node for ScriptNode {
+    fn desc(&self) -> &NodeDesc {
+        &self.desc
+    }
+
+    fn kind(&self) -> &'static str {
+        self.desc.label
+    }
+
+    fn view(&mut self, node: NodeId) -> Element<'_, Box<dyn DynMessage>, Renderer> {
+        let _ = node;
+        if self.script.params.is_empty() {
+            return Text::new(self.desc.label).into();
+        }
+
+        self.states
+            .iter_mut()
+            .zip(self.values.iter())
+            .zip(self.script.params.iter())
+            .enumerate()
+            .fold(
+                Column::new().spacing(2),
+                |column, (index, ((state, &value), (name, _)))| {
+                    let field = TextInput::new(state, name, &format!("{}", value), move |text| {
+                        Box::new(SetParam(index, text.parse().unwrap_or(value))) as Box<dyn DynMessage>
+                    })
+                    .size(FONT_SIZE)
+                    .width(Length::Units(80));
+
+                    let row = Row::new()
+                        .spacing(4)
+                        .push(Text::new(name.as_str()).size(FONT_SIZE))
+                        .push(field);
+                    column.push(row)
+                },
+            )
+            .into()
+    }
+
+    fn update(&mut self, message: &dyn DynMessage) {
+        if let Some(&SetParam(index, value)) = message.as_any().downcast_ref::<SetParam>() {
+            if let Some(slot) = self.values.get_mut(index) {
+                *slot = value;
+            }
+        }
+    }
+
+    fn save_params(&self) -> serde_json::Value {
+        serde_json::json!({ "values": self.values })
+    }
+
+    fn load_params(&mut self, params: &serde_json::Value) {
+        if let Some(values) = params.get("values").and_then(|v| v.as_array()) {
+            for (slot, value) in self.values.iter_mut().zip(values) {
+                if let Some(value) = value.as_f64() {
+                    *slot = value as f32;
+                }
+            }
+        }
+    }
+}
+
+/// Scans `dir` for script files and parses each one, so the add-node menu
+/// can turn every valid script into a selectable node kind at startup.
+/// Files that fail to parse are skipped rather than aborting the scan.
+pub fn scan_scripts(dir: &Path) -> std::io::Result<Vec<Script>> {
+    let mut scripts = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("scm") {
+            continue;
+        }
+        match Script::load(&path) {
+            Ok(script) => scripts.push(script),
+            Err(err) => log::warn!("skipping script {}: {}", path.display(), err),
+        }
+    }
+    Ok(scripts)
+}
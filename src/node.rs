@@ -0,0 +1,88 @@
+use iced_wgpu::Renderer;
+use iced_winit::Element;
+
+slotmap::new_key_type! {
+    /// Identifies a node within a [`crate::controls::node::Graph`].
+    pub struct NodeId;
+}
+
+/// The WGSL value kinds a port can carry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataType {
+    Scalar,
+    Vector2,
+    Vector3,
+    Vector4,
+    Color,
+}
+
+/// Static shape of a node kind: its label/width for layout plus the typed
+/// ports it exposes. Shared by every `impl Node`, whether compiled-in or
+/// script-backed (see `crate::controls::script::ScriptNode`).
+pub struct NodeDesc {
+    pub label: &'static str,
+    pub width: u16,
+    pub inputs: Vec<(&'static str, DataType)>,
+    pub outputs: Vec<(&'static str, DataType)>,
+}
+
+/// Per-node message payload, type-erased so `NodeWidget` can hold any `impl
+/// Node` behind `Box<dyn Node>` without the editor knowing its concrete
+/// message type. Blanket-implemented for anything `Clone + Debug`, which is
+/// what every node's own message enum already derives.
+pub trait DynMessage: std::fmt::Debug {
+    fn clone_box(&self) -> Box<dyn DynMessage>;
+
+    /// Lets `Node::update` recover the concrete message type it emitted from
+    /// its own `view`, via `downcast_ref`.
+    fn as_any(&self) -> &dyn std::any::Any;
+}
+
+impl Clone for Box<dyn DynMessage> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl<T> DynMessage for T
+where
+    T: 'static + Clone + std::fmt::Debug,
+{
+    fn clone_box(&self) -> Box<dyn DynMessage> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// A node kind: its static shape plus how it renders, and how its
+/// parameters survive a save/load round-trip (`crate::controls::persist`).
+pub trait Node {
+    fn desc(&self) -> &NodeDesc;
+    fn view(&mut self, id: NodeId) -> Element<'_, Box<dyn DynMessage>, Renderer>;
+
+    /// Routes a `Message::Dynamic` payload emitted by this node's own `view`
+    /// back into its state. Nodes with no interactive controls of their own
+    /// (most compiled-in kinds) have nothing to do here.
+    fn update(&mut self, message: &dyn DynMessage) {
+        let _ = message;
+    }
+
+    /// Stable name used to rebuild this node kind when loading a saved
+    /// graph; must be unique across every registered kind.
+    fn kind(&self) -> &'static str;
+
+    fn save_params(&self) -> serde_json::Value;
+    fn load_params(&mut self, params: &serde_json::Value);
+}
+
+impl<T> From<T> for Box<dyn Node>
+where
+    T: Node + 'static,
+{
+    fn from(node: T) -> Self {
+        Box::new(node)
+    }
+}